@@ -1,18 +1,23 @@
 use aws_sdk_ec2::operation::describe_instances::DescribeInstancesOutput;
 use aws_sdk_ec2::operation::describe_volumes::DescribeVolumesOutput;
+use aws_sdk_ec2::operation::describe_security_groups::DescribeSecurityGroupsOutput;
 // File: cpi_aws/src/lib.rs
 use lib_cpi::{
     ActionDefinition, ActionResult, CpiExtension, ParamType, param, validation
 };
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::sync::Mutex;
 
 // AWS SDK crates
 use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_ec2::Client;
 use aws_sdk_ec2::config::Region;
 #[allow(unused_imports)]
-use aws_sdk_ec2::types::{Filter, Tag, ResourceType, InstanceType, TagSpecification};
+use aws_sdk_ec2::types::{
+    Filter, Tag, ResourceType, Instance, InstanceType, TagSpecification, IpPermission, IpRange, UserIdGroupPair,
+    InstanceNetworkInterfaceSpecification, BlockDeviceMapping, EbsBlockDevice, VolumeType, SnapshotState,
+};
 
 #[unsafe(no_mangle)]
 #[allow(improper_ctypes_definitions)]
@@ -25,7 +30,10 @@ pub struct AwsExtension {
     name: String,
     provider_type: String,
     default_settings: HashMap<String, Value>,
-    ec2_client: Option<Client>,
+    // Interior mutability lets execute_action's `&self` actually persist clients across calls
+    // instead of rebuilding them every time, and lets each region keep its own client.
+    ec2_clients: Mutex<HashMap<String, Client>>,
+    runtime: tokio::runtime::Runtime,
 }
 
 impl AwsExtension {
@@ -41,30 +49,221 @@ impl AwsExtension {
             name: "ec2".to_string(),
             provider_type: "cloud".to_string(),
             default_settings,
-            ec2_client: None,
+            ec2_clients: Mutex::new(HashMap::new()),
+            runtime: tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime"),
         }
     }
-    
-    // Initialize the EC2 client
-    async fn get_client(&mut self, region_str: Option<&str>) -> Result<Client, String> {
+
+    // Get (or lazily build) the EC2 client for a given region. Each region gets its own
+    // cached client so that calling e.g. list_workers(Some("eu-west-1")) after an earlier
+    // us-east-1 call actually queries eu-west-1 instead of reusing the first client built, and
+    // the cache is shared across calls instead of being thrown away with each cloned extension.
+    async fn get_client(&self, region_str: Option<&str>) -> Result<Client, String> {
         let region = region_str.unwrap_or("us-east-1");
-        
-        if let Some(client) = &self.ec2_client {
+
+        if let Some(client) = self.ec2_clients.lock().unwrap().get(region) {
             return Ok(client.clone());
         }
-        
+
         let region_provider = RegionProviderChain::first_try(Region::new(region.to_string()))
             .or_default_provider()
             .or_else(Region::new("us-east-1"));
-            
+
         let shared_config = aws_config::defaults(aws_config::BehaviorVersion::latest()).region(region_provider).load().await;
-        
+
         let client = Client::new(&shared_config);
-        self.ec2_client = Some(client.clone());
-        
+        self.ec2_clients.lock().unwrap().insert(region.to_string(), client.clone());
+
         Ok(client)
     }
     
+    // Polling parameters shared by all state waiters: check every 5s, give up after 60 attempts
+    // (~5 minutes), and require 3 consecutive matching observations before declaring success so
+    // eventually-consistent reads don't cause us to report success on a single lucky poll.
+    const WAIT_POLL_INTERVAL_SECS: u64 = 5;
+    const WAIT_MAX_ATTEMPTS: u32 = 60;
+    const WAIT_CONSECUTIVE_SUCCESSES: u32 = 3;
+
+    // `timeout_secs` overrides the default ~5 minute budget when callers know a transition is
+    // slower or faster than usual; it's converted to a number of polls at the fixed interval.
+    // Clamped to at least WAIT_CONSECUTIVE_SUCCESSES attempts, since the waiters require that
+    // many consecutive successful polls before returning Ok — anything smaller would make a
+    // short timeout_secs guarantee a false failure even when the resource is already settled.
+    fn wait_max_attempts(timeout_secs: Option<u64>) -> u32 {
+        timeout_secs
+            .map(|secs| (secs / Self::WAIT_POLL_INTERVAL_SECS).max(1) as u32)
+            .unwrap_or(Self::WAIT_MAX_ATTEMPTS)
+            .max(Self::WAIT_CONSECUTIVE_SUCCESSES)
+    }
+
+    // Poll DescribeInstances until `instance_id` reaches `target_state`. When waiting for
+    // "terminated", an InvalidInstanceID.NotFound error is treated as success since AWS removes
+    // terminated instances from the default describe view after a while.
+    async fn wait_for_instance_state(&self, client: &Client, instance_id: &str, target_state: &str, timeout_secs: Option<u64>) -> ActionResult {
+        let mut consecutive = 0;
+        let max_attempts = Self::wait_max_attempts(timeout_secs);
+        let mut last_vm_info: Option<Value> = None;
+
+        for _ in 0..max_attempts {
+            let result = client.describe_instances()
+                .instance_ids(instance_id)
+                .send()
+                .await;
+
+            match result {
+                Ok(output) => {
+                    let instance = output.reservations().first()
+                        .and_then(|r| r.instances().first());
+
+                    let state = instance
+                        .and_then(|i| i.state())
+                        .and_then(|s| s.name())
+                        .map(|s| s.as_str());
+
+                    last_vm_info = instance.map(|i| self.build_vm_info(i));
+
+                    if state == Some(target_state) {
+                        consecutive += 1;
+                    } else {
+                        consecutive = 0;
+                    }
+                },
+                // AWS drops terminated instances from the default describe view after a while,
+                // so there's no instance left to build a vm_info from.
+                Err(err) if target_state == "terminated" && format!("{:?}", err).contains("InvalidInstanceID.NotFound") => {
+                    consecutive += 1;
+                    last_vm_info = Some(json!({ "id": instance_id, "state": "terminated" }));
+                },
+                Err(err) => return Err(format!("Failed to poll instance state: {:?}", err)),
+            }
+
+            if consecutive >= Self::WAIT_CONSECUTIVE_SUCCESSES {
+                return Ok(json!({
+                    "success": true,
+                    "id": instance_id,
+                    "state": target_state,
+                    "vm": last_vm_info
+                }));
+            }
+
+            tokio::time::sleep(std::time::Duration::from_secs(Self::WAIT_POLL_INTERVAL_SECS)).await;
+        }
+
+        Err(format!("Timed out waiting for instance {} to reach state '{}'", instance_id, target_state))
+    }
+
+    // Poll DescribeVolumes until `volume_id` reaches `target_state` (e.g. "available", "in-use").
+    async fn wait_for_volume_state(&self, client: &Client, volume_id: &str, target_state: &str, timeout_secs: Option<u64>) -> ActionResult {
+        let mut consecutive = 0;
+        let max_attempts = Self::wait_max_attempts(timeout_secs);
+
+        for _ in 0..max_attempts {
+            let result = client.describe_volumes()
+                .volume_ids(volume_id)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to poll volume state: {:?}", e))?;
+
+            let state = result.volumes().first()
+                .and_then(|v| v.state())
+                .map(|s| s.as_str());
+
+            if state == Some(target_state) {
+                consecutive += 1;
+            } else {
+                consecutive = 0;
+            }
+
+            if consecutive >= Self::WAIT_CONSECUTIVE_SUCCESSES {
+                return Ok(json!({
+                    "success": true,
+                    "id": volume_id,
+                    "state": target_state
+                }));
+            }
+
+            tokio::time::sleep(std::time::Duration::from_secs(Self::WAIT_POLL_INTERVAL_SECS)).await;
+        }
+
+        Err(format!("Timed out waiting for volume {} to reach state '{}'", volume_id, target_state))
+    }
+
+    // Poll DescribeSnapshots until `snapshot_id` reaches `target_state` (e.g. "completed").
+    async fn wait_for_snapshot_state(&self, client: &Client, snapshot_id: &str, target_state: &str, timeout_secs: Option<u64>) -> ActionResult {
+        let mut consecutive = 0;
+        let max_attempts = Self::wait_max_attempts(timeout_secs);
+
+        for _ in 0..max_attempts {
+            let result = client.describe_snapshots()
+                .snapshot_ids(snapshot_id)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to poll snapshot state: {:?}", e))?;
+
+            let state = result.snapshots().first()
+                .and_then(|s| s.state())
+                .map(|s| s.as_str());
+
+            if state == Some(target_state) {
+                consecutive += 1;
+            } else {
+                consecutive = 0;
+            }
+
+            if consecutive >= Self::WAIT_CONSECUTIVE_SUCCESSES {
+                return Ok(json!({
+                    "success": true,
+                    "id": snapshot_id,
+                    "state": target_state
+                }));
+            }
+
+            tokio::time::sleep(std::time::Duration::from_secs(Self::WAIT_POLL_INTERVAL_SECS)).await;
+        }
+
+        Err(format!("Timed out waiting for snapshot {} to reach state '{}'", snapshot_id, target_state))
+    }
+
+    // Stand-alone action so callers that issued an action without `wait: true` can still block
+    // on a state transition later, e.g. after restarting a poll loop of their own.
+    async fn wait_for_worker_state(&self, worker_id: String, target_state: String, region: Option<&str>, timeout_secs: Option<u64>) -> ActionResult {
+        let client = self.get_client(region).await?;
+        self.wait_for_instance_state(&client, &worker_id, &target_state, timeout_secs).await
+    }
+
+    // Helper to pull a required array-of-strings parameter out, e.g. the `worker_ids` list the
+    // batch lifecycle actions take. `lib_cpi::validation` only has scalar extractors today.
+    fn extract_required_string_array(&self, params: &HashMap<String, Value>, key: &str) -> Result<Vec<String>, String> {
+        params.get(key)
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect::<Vec<String>>())
+            .filter(|ids| !ids.is_empty())
+            .ok_or_else(|| format!("Missing or empty required parameter: {}", key))
+    }
+
+    // Shared with wait_for_instance_state so a caller that waited for a transition gets the same
+    // resolved instance description get_worker would give it, instead of having to re-fetch it.
+    fn build_vm_info(&self, instance: &Instance) -> Value {
+        let name = self.get_name_from_tags(instance.tags());
+        let state = instance.state()
+            .and_then(|s| s.name())
+            .map(|s| s.as_str())
+            .unwrap_or("unknown");
+
+        json!({
+            "name": name,
+            "id": instance.instance_id().unwrap_or("unknown"),
+            "state": state,
+            "instance_type": instance.instance_type().map(|t| t.as_str()).unwrap_or("unknown"),
+            "public_ip": instance.public_ip_address(),
+            "private_ip": instance.private_ip_address(),
+            "availability_zone": instance.placement().and_then(|p| p.availability_zone())
+        })
+    }
+
     // Helper function to get name tag from AWS tags
     fn get_name_from_tags(&self, tags: &[Tag]) -> String {
         for tag in tags {
@@ -85,28 +284,28 @@ impl AwsExtension {
     
     // Implementation of individual actions
     
-    // async fn test_install(&mut self) -> ActionResult {
-    //     // Just try to get the EC2 client and list regions to verify credentials work
-    //     let client = self.get_client(None).await?;
-    //     
-    //     let result = client.describe_regions()
-    //         .send()
-    //         .await
-    //         .map_err(|e| format!("Failed to connect to AWS: {:?}", e))?;
-    //     
-    //     let regions = result.regions()
-    //                         .iter()
-    //                         .filter_map(|r| r.region_name().map(|s| s.to_string()))
-    //                         .collect::<Vec<String>>();
-    //     
-    //     Ok(json!({
-    //         "success": true,
-    //         "version": "AWS SDK for Rust",
-    //         "regions": regions
-    //     }))
-    // }
+    async fn test_install(&self, region: Option<&str>) -> ActionResult {
+        // Just try to get the EC2 client and list regions to verify credentials work
+        let client = self.get_client(region).await?;
+
+        let result = client.describe_regions()
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to AWS: {:?}", e))?;
+
+        let regions = result.regions()
+                            .iter()
+                            .filter_map(|r| r.region_name().map(|s| s.to_string()))
+                            .collect::<Vec<String>>();
+
+        Ok(json!({
+            "success": true,
+            "version": "AWS SDK for Rust",
+            "regions": regions
+        }))
+    }
     
-    async fn list_workers(&mut self, region: Option<&str>) -> ActionResult {
+    async fn list_workers(&self, region: Option<&str>) -> ActionResult {
         let client = self.get_client(region).await?;
         
         let result = client.describe_instances()
@@ -152,9 +351,23 @@ impl AwsExtension {
         instances
     }
     
-    async fn create_worker(&mut self, worker_name: String, instance_type: String, ami: String, region: Option<&str>) -> ActionResult {
+    async fn create_worker(
+        &self,
+        worker_name: String,
+        instance_type: String,
+        ami: String,
+        security_group_ids: Option<Vec<String>>,
+        key_name: Option<String>,
+        user_data: Option<String>,
+        subnet_id: Option<String>,
+        associate_public_ip: Option<bool>,
+        root_volume_size_gb: Option<i32>,
+        root_volume_type: Option<String>,
+        region: Option<&str>,
+        wait: bool,
+    ) -> ActionResult {
         let client = self.get_client(region).await?;
-        
+
         // Create tags for the instance
         let tag_specifications = TagSpecification::builder()
             .resource_type(ResourceType::Instance)
@@ -165,32 +378,75 @@ impl AwsExtension {
                     .build()
             )
             .build();
-        
-        // Convert instance type string to enum
-        let instance_type_enum = match instance_type.as_str() {
-            "t2.micro" => InstanceType::T2Micro,
-            "t2.small" => InstanceType::T2Small,
-            "t2.medium" => InstanceType::T2Medium,
-            "t3.micro" => InstanceType::T3Micro,
-            "t3.small" => InstanceType::T3Small,
-            "t3.medium" => InstanceType::T3Medium,
-            "m5.large" => InstanceType::M5Large,
-            "m5.xlarge" => InstanceType::M5Xlarge,
-            _ => InstanceType::T2Micro, // Default to t2.micro if not matched
-        };
-        
-        let result = client.run_instances()
-            .image_id(ami)
+
+        // Accept any instance type AWS knows about instead of the fixed list we used to hard-code.
+        let instance_type_enum = InstanceType::from(instance_type.as_str());
+
+        let mut request = client.run_instances()
+            .image_id(ami.clone())
             .instance_type(instance_type_enum)
             .min_count(1)
             .max_count(1)
             .tag_specifications(tag_specifications)
+            .set_key_name(key_name)
+            .set_user_data(user_data);
+
+        // Associating a public IP requires the subnet to be expressed via a network interface
+        // specification rather than the top-level subnet_id/security_group_ids fields.
+        if let Some(subnet_id) = subnet_id {
+            let mut nic = InstanceNetworkInterfaceSpecification::builder()
+                .subnet_id(subnet_id)
+                .device_index(0)
+                .associate_public_ip_address(associate_public_ip.unwrap_or(false));
+
+            if let Some(security_group_ids) = security_group_ids {
+                nic = nic.set_groups(Some(security_group_ids));
+            }
+
+            request = request.network_interfaces(nic.build());
+        } else {
+            request = request.set_security_group_ids(security_group_ids);
+        }
+
+        if let Some(root_volume_size_gb) = root_volume_size_gb {
+            // Root device names vary by AMI (Amazon Linux uses /dev/xvda, Ubuntu HVM and many
+            // others use /dev/sda1) — a BlockDeviceMapping for the wrong name attaches an extra
+            // blank data volume instead of resizing the root, so ask the AMI itself.
+            let images = client.describe_images()
+                .image_ids(ami.clone())
+                .send()
+                .await
+                .map_err(|e| format!("Failed to describe AMI {}: {:?}", ami, e))?;
+
+            let root_device_name = images.images().first()
+                .and_then(|image| image.root_device_name())
+                .ok_or_else(|| format!("Could not resolve root device name for AMI {}", ami))?
+                .to_string();
+
+            let ebs = EbsBlockDevice::builder()
+                .volume_size(root_volume_size_gb)
+                .volume_type(VolumeType::from(root_volume_type.unwrap_or_else(|| "gp2".to_string()).as_str()))
+                .build();
+
+            let block_device_mapping = BlockDeviceMapping::builder()
+                .device_name(root_device_name)
+                .ebs(ebs)
+                .build();
+
+            request = request.block_device_mappings(block_device_mapping);
+        }
+
+        let result = request
             .send()
             .await
             .map_err(|e| format!("Failed to create EC2 instance: {:?}", e))?;
-        
+
         if let Some(instance) = result.instances().first() {
             if let Some(instance_id) = instance.instance_id() {
+                if wait {
+                    self.wait_for_instance_state(&client, instance_id, "running", None).await?;
+                }
+
                 return Ok(json!({
                     "success": true,
                     "id": instance_id,
@@ -198,25 +454,29 @@ impl AwsExtension {
                 }));
             }
         }
-        
+
         Err("No instance was created".to_string())
     }
-    
-    async fn delete_worker(&mut self, worker_id: String, region: Option<&str>) -> ActionResult {
+
+    async fn delete_worker(&self, worker_id: String, region: Option<&str>, wait: bool) -> ActionResult {
         let client = self.get_client(region).await?;
-        
+
         client.terminate_instances()
             .instance_ids(worker_id.clone())
             .send()
             .await
             .map_err(|e| format!("Failed to terminate EC2 instance: {:?}", e))?;
-        
+
+        if wait {
+            self.wait_for_instance_state(&client, &worker_id, "terminated", None).await?;
+        }
+
         Ok(json!({
             "success": true
         }))
     }
     
-    async fn get_worker(&mut self, worker_id: String, region: Option<&str>) -> ActionResult {
+    async fn get_worker(&self, worker_id: String, region: Option<&str>) -> ActionResult {
         let client = self.get_client(region).await?;
         
         let result = client.describe_instances()
@@ -227,33 +487,17 @@ impl AwsExtension {
         
         if let Some(reservation) = result.reservations().first() {
             if let Some(instance) = reservation.instances().first() {
-                let name = self.get_name_from_tags(instance.tags());
-                let state = instance.state()
-                            .and_then(|s| s.name())
-                            .map(|s| s.as_str())
-                            .unwrap_or("unknown");
-                
-                let vm_info = json!({
-                    "name": name,
-                    "id": instance.instance_id().unwrap_or("unknown"),
-                    "state": state,
-                    "instance_type": instance.instance_type().map(|t| t.as_str()).unwrap_or("unknown"),
-                    "public_ip": instance.public_ip_address(),
-                    "private_ip": instance.private_ip_address(),
-                    "availability_zone": instance.placement().and_then(|p| p.availability_zone())
-                });
-                
                 return Ok(json!({
                     "success": true,
-                    "vm": vm_info
+                    "vm": self.build_vm_info(instance)
                 }));
             }
         }
-        
+
         Err(format!("Instance with ID {} not found", worker_id))
     }
     
-    async fn has_worker(&mut self, worker_id: String, region: Option<&str>) -> ActionResult {
+    async fn has_worker(&self, worker_id: String, region: Option<&str>) -> ActionResult {
         let client = self.get_client(region).await?;
         
         let result = client.describe_instances()
@@ -286,22 +530,246 @@ impl AwsExtension {
         }
     }
     
-    async fn start_worker(&mut self, worker_id: String, region: Option<&str>) -> ActionResult {
+    async fn start_worker(&self, worker_id: String, region: Option<&str>, wait: bool, timeout_secs: Option<u64>) -> ActionResult {
         let client = self.get_client(region).await?;
-        
+
         client.start_instances()
             .instance_ids(worker_id.clone())
             .send()
             .await
             .map_err(|e| format!("Failed to start EC2 instance: {:?}", e))?;
-        
+
+        if wait {
+            self.wait_for_instance_state(&client, &worker_id, "running", timeout_secs).await?;
+        }
+
         Ok(json!({
             "success": true,
             "started": worker_id
         }))
     }
-    
-    async fn get_volumes(&mut self, region: Option<&str>) -> ActionResult {
+
+    // Counterpart to start_worker: pauses billing-heavy instances without terminating them, so
+    // they can later be resumed with start_worker. `force` skips a clean OS shutdown;
+    // `hibernate` persists RAM to the root volume instead of a normal stop, where supported.
+    async fn stop_worker(&self, worker_id: String, force: bool, hibernate: bool, region: Option<&str>, wait: bool, timeout_secs: Option<u64>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        client.stop_instances()
+            .instance_ids(worker_id.clone())
+            .force(force)
+            .hibernate(hibernate)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to stop EC2 instance: {:?}", e))?;
+
+        if wait {
+            self.wait_for_instance_state(&client, &worker_id, "stopped", timeout_secs).await?;
+        }
+
+        Ok(json!({
+            "success": true,
+            "id": worker_id.clone(),
+            "stopped": worker_id
+        }))
+    }
+
+    // Batch counterpart to start_worker: one StartInstances call for the whole list instead of
+    // N round-trips. AWS validates the whole id list atomically, so a single bad/wrong-state id
+    // 400s the entire call — when that happens, fall back to one StartInstances call per id so a
+    // partial failure still reports which ids actually succeeded instead of failing them all.
+    async fn start_workers(&self, worker_ids: Vec<String>, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        let result = client.start_instances()
+            .set_instance_ids(Some(worker_ids.clone()))
+            .send()
+            .await;
+
+        let results = match result {
+            Ok(output) => {
+                worker_ids.iter().map(|id| {
+                    let state = output.starting_instances().iter()
+                        .find(|change| change.instance_id() == Some(id.as_str()))
+                        .and_then(|change| change.current_state())
+                        .and_then(|s| s.name())
+                        .map(|s| s.as_str());
+
+                    json!({ "id": id, "success": state.is_some(), "state": state })
+                }).collect::<Vec<_>>()
+            },
+            Err(_) => {
+                let mut per_id_results = Vec::with_capacity(worker_ids.len());
+                for id in &worker_ids {
+                    let per_id_result = client.start_instances()
+                        .instance_ids(id.clone())
+                        .send()
+                        .await;
+
+                    per_id_results.push(match per_id_result {
+                        Ok(output) => {
+                            let state = output.starting_instances().first()
+                                .and_then(|change| change.current_state())
+                                .and_then(|s| s.name())
+                                .map(|s| s.as_str());
+
+                            json!({ "id": id, "success": state.is_some(), "state": state })
+                        },
+                        Err(err) => json!({ "id": id, "success": false, "error": format!("Failed to start EC2 instance {}: {:?}", id, err) }),
+                    });
+                }
+                per_id_results
+            }
+        };
+
+        let success = results.iter().all(|r| r["success"].as_bool().unwrap_or(false));
+        Ok(json!({ "success": success, "results": results }))
+    }
+
+    // Batch counterpart to stop_worker. Same atomicity caveat as start_workers: StopInstances
+    // validates the whole id list together, so on error we retry per id so a partial failure
+    // reports which ids actually stopped instead of failing them all.
+    async fn stop_workers(&self, worker_ids: Vec<String>, force: bool, hibernate: bool, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        let result = client.stop_instances()
+            .set_instance_ids(Some(worker_ids.clone()))
+            .force(force)
+            .hibernate(hibernate)
+            .send()
+            .await;
+
+        let results = match result {
+            Ok(output) => {
+                worker_ids.iter().map(|id| {
+                    let state = output.stopping_instances().iter()
+                        .find(|change| change.instance_id() == Some(id.as_str()))
+                        .and_then(|change| change.current_state())
+                        .and_then(|s| s.name())
+                        .map(|s| s.as_str());
+
+                    json!({ "id": id, "success": state.is_some(), "state": state })
+                }).collect::<Vec<_>>()
+            },
+            Err(_) => {
+                let mut per_id_results = Vec::with_capacity(worker_ids.len());
+                for id in &worker_ids {
+                    let per_id_result = client.stop_instances()
+                        .instance_ids(id.clone())
+                        .force(force)
+                        .hibernate(hibernate)
+                        .send()
+                        .await;
+
+                    per_id_results.push(match per_id_result {
+                        Ok(output) => {
+                            let state = output.stopping_instances().first()
+                                .and_then(|change| change.current_state())
+                                .and_then(|s| s.name())
+                                .map(|s| s.as_str());
+
+                            json!({ "id": id, "success": state.is_some(), "state": state })
+                        },
+                        Err(err) => json!({ "id": id, "success": false, "error": format!("Failed to stop EC2 instance {}: {:?}", id, err) }),
+                    });
+                }
+                per_id_results
+            }
+        };
+
+        let success = results.iter().all(|r| r["success"].as_bool().unwrap_or(false));
+        Ok(json!({ "success": success, "results": results }))
+    }
+
+    // Batch counterpart to reboot_worker. RebootInstances has no per-instance response payload,
+    // so a successful batch call is reported as success for every id; on error we retry per id
+    // so a partial failure reports which ids actually rebooted instead of failing them all.
+    async fn reboot_workers(&self, worker_ids: Vec<String>, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        let result = client.reboot_instances()
+            .set_instance_ids(Some(worker_ids.clone()))
+            .send()
+            .await;
+
+        let results = match result {
+            Ok(_) => {
+                worker_ids.iter()
+                    .map(|id| json!({ "id": id, "success": true }))
+                    .collect::<Vec<_>>()
+            },
+            Err(_) => {
+                let mut per_id_results = Vec::with_capacity(worker_ids.len());
+                for id in &worker_ids {
+                    let per_id_result = client.reboot_instances()
+                        .instance_ids(id.clone())
+                        .send()
+                        .await;
+
+                    per_id_results.push(match per_id_result {
+                        Ok(_) => json!({ "id": id, "success": true }),
+                        Err(err) => json!({ "id": id, "success": false, "error": format!("Failed to reboot EC2 instance {}: {:?}", id, err) }),
+                    });
+                }
+                per_id_results
+            }
+        };
+
+        let success = results.iter().all(|r| r["success"].as_bool().unwrap_or(false));
+        Ok(json!({ "success": success, "results": results }))
+    }
+
+    // Batch counterpart to delete_worker. Same atomicity caveat: on error we retry per id so a
+    // partial failure reports which ids actually terminated instead of failing them all.
+    async fn delete_workers(&self, worker_ids: Vec<String>, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        let result = client.terminate_instances()
+            .set_instance_ids(Some(worker_ids.clone()))
+            .send()
+            .await;
+
+        let results = match result {
+            Ok(output) => {
+                worker_ids.iter().map(|id| {
+                    let state = output.terminating_instances().iter()
+                        .find(|change| change.instance_id() == Some(id.as_str()))
+                        .and_then(|change| change.current_state())
+                        .and_then(|s| s.name())
+                        .map(|s| s.as_str());
+
+                    json!({ "id": id, "success": state.is_some(), "state": state })
+                }).collect::<Vec<_>>()
+            },
+            Err(_) => {
+                let mut per_id_results = Vec::with_capacity(worker_ids.len());
+                for id in &worker_ids {
+                    let per_id_result = client.terminate_instances()
+                        .instance_ids(id.clone())
+                        .send()
+                        .await;
+
+                    per_id_results.push(match per_id_result {
+                        Ok(output) => {
+                            let state = output.terminating_instances().first()
+                                .and_then(|change| change.current_state())
+                                .and_then(|s| s.name())
+                                .map(|s| s.as_str());
+
+                            json!({ "id": id, "success": state.is_some(), "state": state })
+                        },
+                        Err(err) => json!({ "id": id, "success": false, "error": format!("Failed to terminate EC2 instance {}: {:?}", id, err) }),
+                    });
+                }
+                per_id_results
+            }
+        };
+
+        let success = results.iter().all(|r| r["success"].as_bool().unwrap_or(false));
+        Ok(json!({ "success": success, "results": results }))
+    }
+
+    async fn get_volumes(&self, region: Option<&str>) -> ActionResult {
         let client = self.get_client(region).await?;
         
         let result = client.describe_volumes()
@@ -309,7 +777,7 @@ impl AwsExtension {
             .await
             .map_err(|e| format!("Failed to list EBS volumes: {:?}", e))?;
         
-        let volumes = self.parse_ec2_volumes(&result);
+        let volumes = self.parse_ec2_volumes(&result, None);
         
         Ok(json!({
             "success": true,
@@ -318,16 +786,18 @@ impl AwsExtension {
     }
     
     // Helper to map EC2 volumes API response to our simplified form
-    fn parse_ec2_volumes(&self, output: &DescribeVolumesOutput) -> Vec<Value> {
+    // `scoped_to_instance` additionally includes the attachment's device name, which only makes
+    // sense when the caller has already filtered the volumes down to a single instance.
+    fn parse_ec2_volumes(&self, output: &DescribeVolumesOutput, scoped_to_instance: Option<&str>) -> Vec<Value> {
         let mut volumes = Vec::new();
-        
+
         for volume in output.volumes() {
             if let Some(volume_id) = volume.volume_id() {
                 let attached_to = volume.attachments()
                     .get(0)
                     .and_then(|attachment| attachment.instance_id().map(|id| id.to_string()));
-                
-                let vol = json!({
+
+                let mut vol = json!({
                     "id": volume_id,
                     "path": volume_id,  // Using volume_id as path for consistency with other providers
                     "size_mb": (volume.size().unwrap_or(0) * 1024) as i64,  // Convert GB to MB
@@ -335,15 +805,111 @@ impl AwsExtension {
                     "availability_zone": volume.availability_zone().unwrap_or("unknown"),
                     "attached_to": attached_to
                 });
-                
+
+                if let Some(instance_id) = scoped_to_instance {
+                    let device_name = volume.attachments().iter()
+                        .find(|attachment| attachment.instance_id() == Some(instance_id))
+                        .and_then(|attachment| attachment.device());
+                    vol["device_name"] = json!(device_name);
+                }
+
                 volumes.push(vol);
             }
         }
-        
+
         volumes
     }
     
-    async fn has_volume(&mut self, volume_id: String, region: Option<&str>) -> ActionResult {
+    // Like get_volumes but scoped to a single instance, with the attachment device name
+    // included so restores can be mapped back onto the original mount points.
+    async fn get_volumes_by_instance(&self, worker_id: String, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        let result = client.describe_volumes()
+            .filters(Filter::builder().name("attachment.instance-id").values(worker_id.clone()).build())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to list volumes for instance {}: {:?}", worker_id, e))?;
+
+        let volumes = self.parse_ec2_volumes(&result, Some(&worker_id));
+
+        Ok(json!({
+            "success": true,
+            "volumes": volumes
+        }))
+    }
+
+    // Back up every volume attached to an instance in one call, tagging each snapshot with the
+    // source volume's own tags plus a DeviceName tag so a later restore can map snapshots back
+    // onto their original mount points. `exclude` may list either volume ids or device names.
+    async fn snapshot_instance(&self, worker_id: String, exclude: Vec<String>, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        let result = client.describe_volumes()
+            .filters(Filter::builder().name("attachment.instance-id").values(worker_id.clone()).build())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to list volumes for instance {}: {:?}", worker_id, e))?;
+
+        let mut snapshots = Vec::new();
+
+        for volume in result.volumes() {
+            if let Some(volume_id) = volume.volume_id() {
+                let device_name = volume.attachments().iter()
+                    .find(|attachment| attachment.instance_id() == Some(worker_id.as_str()))
+                    .and_then(|attachment| attachment.device());
+
+                if exclude.iter().any(|e| e == volume_id || device_name == Some(e.as_str())) {
+                    continue;
+                }
+
+                // aws:-prefixed keys are reserved (e.g. ASG/CFN-managed volumes carry them) and
+                // CreateSnapshot rejects the whole call if a TagSpecification includes one.
+                let mut tags = volume.tags().iter()
+                    .filter(|tag| !tag.key().unwrap_or("").starts_with("aws:"))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if let Some(device_name) = device_name {
+                    tags.push(Tag::builder().key("DeviceName").value(device_name).build());
+                }
+
+                let desc = format!("Snapshot of {} (instance {})", volume_id, worker_id);
+                let snapshot_result = client.create_snapshot()
+                    .volume_id(volume_id)
+                    .description(desc)
+                    .tag_specifications(
+                        {
+                            let mut builder = TagSpecification::builder().resource_type(ResourceType::Snapshot);
+                            for tag in tags {
+                                builder = builder.tags(tag);
+                            }
+                            builder.build()
+                        }
+                    )
+                    .send()
+                    .await
+                    .map_err(|e| format!("Failed to snapshot volume {}: {:?}", volume_id, e))?;
+
+                let snapshot_id = snapshot_result.snapshot_id()
+                    .ok_or_else(|| format!("No snapshot ID was returned for volume {}", volume_id))?
+                    .to_string();
+
+                snapshots.push(json!({
+                    "volume_id": volume_id,
+                    "device_name": device_name,
+                    "snapshot_id": snapshot_id
+                }));
+            }
+        }
+
+        Ok(json!({
+            "success": true,
+            "worker_id": worker_id,
+            "snapshots": snapshots
+        }))
+    }
+
+    async fn has_volume(&self, volume_id: String, region: Option<&str>) -> ActionResult {
         let client = self.get_client(region).await?;
         
         let result = client.describe_volumes()
@@ -374,9 +940,9 @@ impl AwsExtension {
         }
     }
     
-    async fn create_volume(&mut self, size_gb: i64, availability_zone: String, volume_type: String, region: Option<&str>) -> ActionResult {
+    async fn create_volume(&self, size_gb: i64, availability_zone: String, volume_type: String, region: Option<&str>, wait: bool, timeout_secs: Option<u64>) -> ActionResult {
         let client = self.get_client(region).await?;
-        
+
         let result = client.create_volume()
             .availability_zone(availability_zone)
             .size(size_gb as i32)
@@ -384,19 +950,23 @@ impl AwsExtension {
             .send()
             .await
             .map_err(|e| format!("Failed to create EBS volume: {:?}", e))?;
-        
+
         if let Some(volume_id) = result.volume_id() {
+            if wait {
+                self.wait_for_volume_state(&client, volume_id, "available", timeout_secs).await?;
+            }
+
             return Ok(json!({
                 "success": true,
                 "id": volume_id,
                 "path": volume_id
             }));
         }
-        
+
         Err("No volume ID was returned".to_string())
     }
     
-    async fn delete_volume(&mut self, volume_id: String, region: Option<&str>) -> ActionResult {
+    async fn delete_volume(&self, volume_id: String, region: Option<&str>) -> ActionResult {
         let client = self.get_client(region).await?;
         
         client.delete_volume()
@@ -410,37 +980,45 @@ impl AwsExtension {
         }))
     }
     
-    async fn attach_volume(&mut self, worker_id: String, volume_id: String, device_name: String, region: Option<&str>) -> ActionResult {
+    async fn attach_volume(&self, worker_id: String, volume_id: String, device_name: String, region: Option<&str>, wait: bool) -> ActionResult {
         let client = self.get_client(region).await?;
-        
+
         client.attach_volume()
             .instance_id(worker_id)
-            .volume_id(volume_id)
+            .volume_id(volume_id.clone())
             .device(device_name)
             .send()
             .await
             .map_err(|e| format!("Failed to attach EBS volume: {:?}", e))?;
-        
+
+        if wait {
+            self.wait_for_volume_state(&client, &volume_id, "in-use", None).await?;
+        }
+
         Ok(json!({
             "success": true
         }))
     }
-    
-    async fn detach_volume(&mut self, volume_id: String, region: Option<&str>) -> ActionResult {
+
+    async fn detach_volume(&self, volume_id: String, region: Option<&str>, wait: bool) -> ActionResult {
         let client = self.get_client(region).await?;
-        
+
         client.detach_volume()
-            .volume_id(volume_id)
+            .volume_id(volume_id.clone())
             .send()
             .await
             .map_err(|e| format!("Failed to detach EBS volume: {:?}", e))?;
-        
+
+        if wait {
+            self.wait_for_volume_state(&client, &volume_id, "available", None).await?;
+        }
+
         Ok(json!({
             "success": true
         }))
     }
     
-    async fn create_snapshot(&mut self, volume_id: String, snapshot_name: String, region: Option<&str>) -> ActionResult {
+    async fn create_snapshot(&self, volume_id: String, snapshot_name: String, region: Option<&str>, wait: bool, timeout_secs: Option<u64>) -> ActionResult {
         let client = self.get_client(region).await?;
         
         // Add tags for the snapshot
@@ -467,111 +1045,435 @@ impl AwsExtension {
             )
             .send()
             .await
-            .map_err(|e| format!("Failed to create snapshot: {:?}", e))?;
-        
-        if let Some(snapshot_id) = result.snapshot_id() {
-            return Ok(json!({
-                "success": true,
-                "id": snapshot_id
-            }));
-        }
-        
-        Err("No snapshot ID was returned".to_string())
+            .map_err(|e| format!("Failed to create snapshot: {:?}", e))?;
+        
+        if let Some(snapshot_id) = result.snapshot_id() {
+            if wait {
+                self.wait_for_snapshot_state(&client, snapshot_id, "completed", timeout_secs).await?;
+            }
+
+            return Ok(json!({
+                "success": true,
+                "id": snapshot_id
+            }));
+        }
+
+        Err("No snapshot ID was returned".to_string())
+    }
+
+    // Copy a snapshot from one region into another, e.g. for DR replication or promoting an
+    // AMI's backing snapshot to a second region. The source and destination regions can differ,
+    // so this needs its own client bound to the destination region.
+    async fn copy_snapshot(&self, source_snapshot_id: String, source_region: String, destination_region: Option<&str>, name: Option<String>, description: Option<String>) -> ActionResult {
+        let client = self.get_client(destination_region).await?;
+        let destination_region = destination_region.unwrap_or("us-east-1").to_string();
+
+        let desc = description.unwrap_or_else(|| format!("Copy of {} from {}", source_snapshot_id, source_region));
+
+        let result = client.copy_snapshot()
+            .source_region(source_region.clone())
+            .source_snapshot_id(source_snapshot_id.clone())
+            .description(desc)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to copy snapshot: {:?}", e))?;
+
+        let snapshot_id = result.snapshot_id().ok_or("No snapshot ID was returned for the copy")?.to_string();
+
+        if let Some(name) = name {
+            let tag = Tag::builder()
+                .key("Name")
+                .value(name)
+                .build();
+
+            client.create_tags()
+                .resources(snapshot_id.clone())
+                .tags(tag)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to tag copied snapshot: {:?}", e))?;
+        }
+
+        Ok(json!({
+            "success": true,
+            "id": snapshot_id,
+            "source_snapshot_id": source_snapshot_id,
+            "source_region": source_region,
+            "destination_region": destination_region
+        }))
+    }
+
+    async fn delete_snapshot(&self, snapshot_id: String, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+        
+        client.delete_snapshot()
+            .snapshot_id(snapshot_id)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to delete snapshot: {:?}", e))?;
+        
+        Ok(json!({
+            "success": true
+        }))
+    }
+    
+    async fn has_snapshot(&self, snapshot_id: String, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+        
+        let result = client.describe_snapshots()
+            .snapshot_ids(snapshot_id.clone())
+            .send()
+            .await;
+        
+        match result {
+            Ok(output) => {
+                let exists = !output.snapshots().is_empty();
+                
+                Ok(json!({
+                    "success": true,
+                    "exists": exists
+                }))
+            },
+            Err(err) => {
+                // Check if the error is a "not found" error
+                if format!("{:?}", err).contains("InvalidSnapshot.NotFound") {
+                    Ok(json!({
+                        "success": true,
+                        "exists": false
+                    }))
+                } else {
+                    Err(format!("Failed to check if snapshot exists: {:?}", err))
+                }
+            }
+        }
+    }
+    
+    async fn reboot_worker(&self, worker_id: String, region: Option<&str>, wait: bool, timeout_secs: Option<u64>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        client.reboot_instances()
+            .instance_ids(worker_id.clone())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reboot EC2 instance: {:?}", e))?;
+
+        if wait {
+            self.wait_for_instance_state(&client, &worker_id, "running", timeout_secs).await?;
+        }
+
+        Ok(json!({
+            "success": true
+        }))
+    }
+    
+    async fn set_worker_metadata(&self, worker_id: String, key: String, value: String, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+        
+        let tag = Tag::builder()
+            .key(key)
+            .value(value)
+            .build();
+        
+        client.create_tags()
+            .resources(worker_id.clone())
+            .tags(tag)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to set instance metadata: {:?}", e))?;
+        
+        Ok(json!({
+            "success": true
+        }))
+    }
+    
+    async fn snapshot_volume(&self, source_volume_id: String, snapshot_name: String, region: Option<&str>, wait: bool, timeout_secs: Option<u64>) -> ActionResult {
+        // First create a snapshot of the source volume
+        let snapshot_result = self.create_snapshot(source_volume_id.clone(), snapshot_name.clone(), region, wait, timeout_secs).await?;
+        
+        // Extract the snapshot ID
+        let snapshot_id = match snapshot_result.get("id") {
+            Some(Value::String(id)) => id.clone(),
+            _ => return Err("Failed to get snapshot ID".to_string()),
+        };
+        
+        Ok(json!({
+            "success": true,
+            "id": snapshot_id,
+            "source_volume_id": source_volume_id
+        }))
+    }
+
+    // Compound "patch broke boot, roll back" runbook: find the instance's root volume, find the
+    // most recent completed snapshot of it, carve a fresh volume from that snapshot, and (unless
+    // the caller only wants the volume created) swap it onto the instance in place of the old one.
+    async fn restore_root_volume(&self, worker_id: String, perform_swap: bool, restart: bool, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        let describe = client.describe_instances()
+            .instance_ids(worker_id.clone())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to describe instance: {:?}", e))?;
+
+        let instance = describe.reservations().first()
+            .and_then(|r| r.instances().first())
+            .ok_or_else(|| format!("Instance with ID {} not found", worker_id))?;
+
+        let availability_zone = instance.placement()
+            .and_then(|p| p.availability_zone())
+            .ok_or("Instance has no availability zone")?
+            .to_string();
+
+        let root_device_name = instance.root_device_name()
+            .ok_or("Instance has no root device name")?
+            .to_string();
+
+        let root_volume_id = instance.block_device_mappings().iter()
+            .find(|mapping| mapping.device_name() == Some(root_device_name.as_str()))
+            .and_then(|mapping| mapping.ebs())
+            .and_then(|ebs| ebs.volume_id())
+            .ok_or("Could not resolve the instance's root volume ID")?
+            .to_string();
+
+        let snapshots = client.describe_snapshots()
+            .filters(Filter::builder().name("volume-id").values(root_volume_id.clone()).build())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to list snapshots for volume {}: {:?}", root_volume_id, e))?;
+
+        let latest_snapshot = snapshots.snapshots().iter()
+            .filter(|snapshot| snapshot.state() == Some(&SnapshotState::Completed))
+            .max_by_key(|snapshot| snapshot.start_time().cloned())
+            .ok_or_else(|| format!("No completed snapshot found for root volume {}", root_volume_id))?;
+
+        let snapshot_id = latest_snapshot.snapshot_id()
+            .ok_or("Snapshot has no ID")?
+            .to_string();
+
+        let new_volume = client.create_volume()
+            .availability_zone(availability_zone)
+            .snapshot_id(snapshot_id.clone())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to create volume from snapshot {}: {:?}", snapshot_id, e))?;
+
+        let new_volume_id = new_volume.volume_id()
+            .ok_or("No volume ID was returned for the restored volume")?
+            .to_string();
+
+        self.wait_for_volume_state(&client, &new_volume_id, "available", None).await?;
+
+        if perform_swap {
+            client.stop_instances()
+                .instance_ids(worker_id.clone())
+                .send()
+                .await
+                .map_err(|e| format!("Failed to stop instance before swapping root volume: {:?}", e))?;
+            self.wait_for_instance_state(&client, &worker_id, "stopped", None).await?;
+
+            client.detach_volume()
+                .volume_id(root_volume_id.clone())
+                .send()
+                .await
+                .map_err(|e| format!("Failed to detach old root volume {}: {:?}", root_volume_id, e))?;
+            self.wait_for_volume_state(&client, &root_volume_id, "available", None).await?;
+
+            client.attach_volume()
+                .instance_id(worker_id.clone())
+                .volume_id(new_volume_id.clone())
+                .device(root_device_name)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to attach restored root volume {}: {:?}", new_volume_id, e))?;
+            self.wait_for_volume_state(&client, &new_volume_id, "in-use", None).await?;
+
+            if restart {
+                client.start_instances()
+                    .instance_ids(worker_id.clone())
+                    .send()
+                    .await
+                    .map_err(|e| format!("Failed to restart instance after root volume swap: {:?}", e))?;
+                self.wait_for_instance_state(&client, &worker_id, "running", None).await?;
+            }
+        }
+
+        Ok(json!({
+            "success": true,
+            "volume_id": new_volume_id,
+            "snapshot_id": snapshot_id,
+            "old_volume_id": root_volume_id,
+            "swapped": perform_swap,
+            "restarted": perform_swap && restart
+        }))
+    }
+
+    // Helper to map a DescribeSecurityGroups response to our simplified form, mirroring
+    // parse_ec2_instances/parse_ec2_volumes.
+    fn parse_security_groups(&self, output: &DescribeSecurityGroupsOutput) -> Vec<Value> {
+        let mut groups = Vec::new();
+
+        for group in output.security_groups() {
+            if let Some(group_id) = group.group_id() {
+                let ingress = group.ip_permissions().iter().map(|perm| self.parse_ip_permission(perm)).collect::<Vec<_>>();
+                let egress = group.ip_permissions_egress().iter().map(|perm| self.parse_ip_permission(perm)).collect::<Vec<_>>();
+
+                groups.push(json!({
+                    "id": group_id,
+                    "name": group.group_name().unwrap_or("unknown"),
+                    "vpc_id": group.vpc_id(),
+                    "description": group.description().unwrap_or(""),
+                    "ingress": ingress,
+                    "egress": egress
+                }));
+            }
+        }
+
+        groups
+    }
+
+    fn parse_ip_permission(&self, perm: &IpPermission) -> Value {
+        json!({
+            "protocol": perm.ip_protocol(),
+            "from_port": perm.from_port(),
+            "to_port": perm.to_port(),
+            "cidrs": perm.ip_ranges().iter().filter_map(|r| r.cidr_ip()).collect::<Vec<_>>(),
+            "source_groups": perm.user_id_group_pairs().iter().filter_map(|g| g.group_id()).collect::<Vec<_>>()
+        })
+    }
+
+    async fn list_security_groups(&self, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        let result = client.describe_security_groups()
+            .send()
+            .await
+            .map_err(|e| format!("Failed to list security groups: {:?}", e))?;
+
+        Ok(json!({
+            "success": true,
+            "security_groups": self.parse_security_groups(&result)
+        }))
+    }
+
+    async fn create_security_group(&self, name: String, description: String, vpc_id: Option<String>, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+
+        let mut request = client.create_security_group()
+            .group_name(name.clone())
+            .description(description);
+
+        if let Some(vpc_id) = vpc_id {
+            request = request.vpc_id(vpc_id);
+        }
+
+        let result = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to create security group: {:?}", e))?;
+
+        let group_id = result.group_id().ok_or("No security group ID was returned")?;
+
+        Ok(json!({
+            "success": true,
+            "id": group_id,
+            "name": name
+        }))
     }
-    
-    async fn delete_snapshot(&mut self, snapshot_id: String, region: Option<&str>) -> ActionResult {
+
+    async fn delete_security_group(&self, group_id: String, region: Option<&str>) -> ActionResult {
         let client = self.get_client(region).await?;
-        
-        client.delete_snapshot()
-            .snapshot_id(snapshot_id)
+
+        client.delete_security_group()
+            .group_id(group_id)
             .send()
             .await
-            .map_err(|e| format!("Failed to delete snapshot: {:?}", e))?;
-        
+            .map_err(|e| format!("Failed to delete security group: {:?}", e))?;
+
         Ok(json!({
             "success": true
         }))
     }
-    
-    async fn has_snapshot(&mut self, snapshot_id: String, region: Option<&str>) -> ActionResult {
+
+    // Shared by authorize_ingress/authorize_egress/revoke_ingress/revoke_egress: build the single
+    // IpPermission the caller described, either CIDR-based or referencing another security group.
+    fn build_ip_permission(&self, protocol: String, from_port: i32, to_port: i32, cidr: Option<String>, source_group_id: Option<String>) -> IpPermission {
+        let mut builder = IpPermission::builder()
+            .ip_protocol(protocol)
+            .from_port(from_port)
+            .to_port(to_port);
+
+        if let Some(cidr) = cidr {
+            builder = builder.ip_ranges(IpRange::builder().cidr_ip(cidr).build());
+        }
+
+        if let Some(source_group_id) = source_group_id {
+            builder = builder.user_id_group_pairs(UserIdGroupPair::builder().group_id(source_group_id).build());
+        }
+
+        builder.build()
+    }
+
+    async fn authorize_ingress(&self, group_id: String, protocol: String, from_port: i32, to_port: i32, cidr: Option<String>, source_group_id: Option<String>, region: Option<&str>) -> ActionResult {
         let client = self.get_client(region).await?;
-        
-        let result = client.describe_snapshots()
-            .snapshot_ids(snapshot_id.clone())
+        let permission = self.build_ip_permission(protocol, from_port, to_port, cidr, source_group_id);
+
+        client.authorize_security_group_ingress()
+            .group_id(group_id)
+            .ip_permissions(permission)
             .send()
-            .await;
-        
-        match result {
-            Ok(output) => {
-                let exists = !output.snapshots().is_empty();
-                
-                Ok(json!({
-                    "success": true,
-                    "exists": exists
-                }))
-            },
-            Err(err) => {
-                // Check if the error is a "not found" error
-                if format!("{:?}", err).contains("InvalidSnapshot.NotFound") {
-                    Ok(json!({
-                        "success": true,
-                        "exists": false
-                    }))
-                } else {
-                    Err(format!("Failed to check if snapshot exists: {:?}", err))
-                }
-            }
-        }
+            .await
+            .map_err(|e| format!("Failed to authorize ingress rule: {:?}", e))?;
+
+        Ok(json!({
+            "success": true
+        }))
     }
-    
-    async fn reboot_worker(&mut self, worker_id: String, region: Option<&str>) -> ActionResult {
+
+    async fn authorize_egress(&self, group_id: String, protocol: String, from_port: i32, to_port: i32, cidr: Option<String>, source_group_id: Option<String>, region: Option<&str>) -> ActionResult {
         let client = self.get_client(region).await?;
-        
-        client.reboot_instances()
-            .instance_ids(worker_id.clone())
+        let permission = self.build_ip_permission(protocol, from_port, to_port, cidr, source_group_id);
+
+        client.authorize_security_group_egress()
+            .group_id(group_id)
+            .ip_permissions(permission)
             .send()
             .await
-            .map_err(|e| format!("Failed to reboot EC2 instance: {:?}", e))?;
-        
+            .map_err(|e| format!("Failed to authorize egress rule: {:?}", e))?;
+
         Ok(json!({
             "success": true
         }))
     }
-    
-    async fn set_worker_metadata(&mut self, worker_id: String, key: String, value: String, region: Option<&str>) -> ActionResult {
+
+    async fn revoke_ingress(&self, group_id: String, protocol: String, from_port: i32, to_port: i32, cidr: Option<String>, source_group_id: Option<String>, region: Option<&str>) -> ActionResult {
         let client = self.get_client(region).await?;
-        
-        let tag = Tag::builder()
-            .key(key)
-            .value(value)
-            .build();
-        
-        client.create_tags()
-            .resources(worker_id.clone())
-            .tags(tag)
+        let permission = self.build_ip_permission(protocol, from_port, to_port, cidr, source_group_id);
+
+        client.revoke_security_group_ingress()
+            .group_id(group_id)
+            .ip_permissions(permission)
             .send()
             .await
-            .map_err(|e| format!("Failed to set instance metadata: {:?}", e))?;
-        
+            .map_err(|e| format!("Failed to revoke ingress rule: {:?}", e))?;
+
         Ok(json!({
             "success": true
         }))
     }
-    
-    async fn snapshot_volume(&mut self, source_volume_id: String, snapshot_name: String, region: Option<&str>) -> ActionResult {
-        // First create a snapshot of the source volume
-        let snapshot_result = self.create_snapshot(source_volume_id.clone(), snapshot_name.clone(), region).await?;
-        
-        // Extract the snapshot ID
-        let snapshot_id = match snapshot_result.get("id") {
-            Some(Value::String(id)) => id.clone(),
-            _ => return Err("Failed to get snapshot ID".to_string()),
-        };
-        
+
+    async fn revoke_egress(&self, group_id: String, protocol: String, from_port: i32, to_port: i32, cidr: Option<String>, source_group_id: Option<String>, region: Option<&str>) -> ActionResult {
+        let client = self.get_client(region).await?;
+        let permission = self.build_ip_permission(protocol, from_port, to_port, cidr, source_group_id);
+
+        client.revoke_security_group_egress()
+            .group_id(group_id)
+            .ip_permissions(permission)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to revoke egress rule: {:?}", e))?;
+
         Ok(json!({
-            "success": true,
-            "id": snapshot_id,
-            "source_volume_id": source_volume_id
+            "success": true
         }))
     }
 }
@@ -592,21 +1494,38 @@ impl CpiExtension for AwsExtension {
             "list_workers".to_string(),
             "create_worker".to_string(),
             "delete_worker".to_string(),
+            "wait_for_worker_state".to_string(),
             "get_worker".to_string(),
             "has_worker".to_string(),
             "start_worker".to_string(),
+            "stop_worker".to_string(),
+            "start_workers".to_string(),
+            "stop_workers".to_string(),
+            "reboot_workers".to_string(),
+            "delete_workers".to_string(),
             "get_volumes".to_string(),
+            "get_volumes_by_instance".to_string(),
+            "snapshot_instance".to_string(),
             "has_volume".to_string(),
             "create_volume".to_string(),
             "delete_volume".to_string(),
             "attach_volume".to_string(),
             "detach_volume".to_string(),
             "create_snapshot".to_string(),
+            "copy_snapshot".to_string(),
             "delete_snapshot".to_string(),
             "has_snapshot".to_string(),
             "reboot_worker".to_string(),
             "set_worker_metadata".to_string(),
             "snapshot_volume".to_string(),
+            "restore_root_volume".to_string(),
+            "list_security_groups".to_string(),
+            "create_security_group".to_string(),
+            "delete_security_group".to_string(),
+            "authorize_ingress".to_string(),
+            "authorize_egress".to_string(),
+            "revoke_ingress".to_string(),
+            "revoke_egress".to_string(),
         ]
     }
     
@@ -633,7 +1552,15 @@ impl CpiExtension for AwsExtension {
                     param!("worker_name", "Name of the instance to create", ParamType::String, required),
                     param!("instance_type", "EC2 instance type", ParamType::String, optional, json!("t2.micro")),
                     param!("ami", "Amazon Machine Image ID", ParamType::String, optional, json!("ami-0c55b159cbfafe1f0")),
+                    param!("security_group_ids", "List of security group IDs to attach", ParamType::Array, optional, json!([])),
+                    param!("key_name", "SSH key pair name to associate with the instance", ParamType::String, optional, json!("")),
+                    param!("user_data", "Base64-encoded cloud-init/bootstrap script", ParamType::String, optional, json!("")),
+                    param!("subnet_id", "Subnet to launch the instance into", ParamType::String, optional, json!("")),
+                    param!("associate_public_ip", "Assign a public IP (requires subnet_id)", ParamType::Boolean, optional, json!(false)),
+                    param!("root_volume_size_gb", "Root EBS volume size in GB", ParamType::Integer, optional, json!(0)),
+                    param!("root_volume_type", "Root EBS volume type (gp2, gp3, io1, etc.)", ParamType::String, optional, json!("gp2")),
                     param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("wait", "Block until the instance is running", ParamType::Boolean, optional, json!(false)),
                 ],
             }),
             "delete_worker" => Some(ActionDefinition {
@@ -642,6 +1569,17 @@ impl CpiExtension for AwsExtension {
                 parameters: vec![
                     param!("worker_id", "ID of the instance to terminate", ParamType::String, required),
                     param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("wait", "Block until the instance is terminated", ParamType::Boolean, optional, json!(false)),
+                ],
+            }),
+            "wait_for_worker_state" => Some(ActionDefinition {
+                name: "wait_for_worker_state".to_string(),
+                description: "Block until an EC2 instance reaches a target state".to_string(),
+                parameters: vec![
+                    param!("worker_id", "ID of the instance", ParamType::String, required),
+                    param!("target_state", "State to wait for (running, stopped, terminated)", ParamType::String, optional, json!("running")),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("timeout_secs", "Override the default ~5 minute polling timeout", ParamType::Integer, optional, json!(300)),
                 ],
             }),
             "get_worker" => Some(ActionDefinition {
@@ -666,6 +1604,54 @@ impl CpiExtension for AwsExtension {
                 parameters: vec![
                     param!("worker_id", "ID of the instance to start", ParamType::String, required),
                     param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("wait", "Block until the instance is running", ParamType::Boolean, optional, json!(false)),
+                    param!("timeout_secs", "Override the default ~5 minute wait timeout", ParamType::Integer, optional, json!(300)),
+                ],
+            }),
+            "stop_worker" => Some(ActionDefinition {
+                name: "stop_worker".to_string(),
+                description: "Stop an EC2 instance without terminating it".to_string(),
+                parameters: vec![
+                    param!("worker_id", "ID of the instance to stop", ParamType::String, required),
+                    param!("force", "Force the stop without a clean OS shutdown", ParamType::Boolean, optional, json!(false)),
+                    param!("hibernate", "Hibernate the instance instead of a normal stop", ParamType::Boolean, optional, json!(false)),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("wait", "Block until the instance is stopped", ParamType::Boolean, optional, json!(false)),
+                    param!("timeout_secs", "Override the default ~5 minute wait timeout", ParamType::Integer, optional, json!(300)),
+                ],
+            }),
+            "start_workers" => Some(ActionDefinition {
+                name: "start_workers".to_string(),
+                description: "Start a batch of EC2 instances in one call".to_string(),
+                parameters: vec![
+                    param!("worker_ids", "IDs of the instances to start", ParamType::Array, required),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "stop_workers" => Some(ActionDefinition {
+                name: "stop_workers".to_string(),
+                description: "Stop a batch of EC2 instances in one call".to_string(),
+                parameters: vec![
+                    param!("worker_ids", "IDs of the instances to stop", ParamType::Array, required),
+                    param!("force", "Force the stop without a clean OS shutdown", ParamType::Boolean, optional, json!(false)),
+                    param!("hibernate", "Hibernate the instances instead of a normal stop", ParamType::Boolean, optional, json!(false)),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "reboot_workers" => Some(ActionDefinition {
+                name: "reboot_workers".to_string(),
+                description: "Reboot a batch of EC2 instances in one call".to_string(),
+                parameters: vec![
+                    param!("worker_ids", "IDs of the instances to reboot", ParamType::Array, required),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "delete_workers" => Some(ActionDefinition {
+                name: "delete_workers".to_string(),
+                description: "Terminate a batch of EC2 instances in one call".to_string(),
+                parameters: vec![
+                    param!("worker_ids", "IDs of the instances to terminate", ParamType::Array, required),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
                 ],
             }),
             "get_volumes" => Some(ActionDefinition {
@@ -675,6 +1661,23 @@ impl CpiExtension for AwsExtension {
                     param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
                 ],
             }),
+            "get_volumes_by_instance" => Some(ActionDefinition {
+                name: "get_volumes_by_instance".to_string(),
+                description: "List the EBS volumes attached to an instance".to_string(),
+                parameters: vec![
+                    param!("worker_id", "ID of the instance", ParamType::String, required),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "snapshot_instance" => Some(ActionDefinition {
+                name: "snapshot_instance".to_string(),
+                description: "Snapshot every EBS volume attached to an instance".to_string(),
+                parameters: vec![
+                    param!("worker_id", "ID of the instance", ParamType::String, required),
+                    param!("exclude", "Volume IDs or device names to skip", ParamType::Array, optional, json!([])),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
             "has_volume" => Some(ActionDefinition {
                 name: "has_volume".to_string(),
                 description: "Check if an EBS volume exists".to_string(),
@@ -691,6 +1694,8 @@ impl CpiExtension for AwsExtension {
                     param!("availability_zone", "Availability zone", ParamType::String, required),
                     param!("volume_type", "Volume type (gp2, io1, etc.)", ParamType::String, optional, json!("gp2")),
                     param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("wait", "Block until the volume is available", ParamType::Boolean, optional, json!(false)),
+                    param!("timeout_secs", "Override the default ~5 minute wait timeout", ParamType::Integer, optional, json!(300)),
                 ],
             }),
             "delete_volume" => Some(ActionDefinition {
@@ -709,6 +1714,7 @@ impl CpiExtension for AwsExtension {
                     param!("volume_id", "ID of the volume", ParamType::String, required),
                     param!("device_name", "Device name (e.g., /dev/sdf)", ParamType::String, required),
                     param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("wait", "Block until the volume is in-use", ParamType::Boolean, optional, json!(false)),
                 ],
             }),
             "detach_volume" => Some(ActionDefinition {
@@ -717,6 +1723,7 @@ impl CpiExtension for AwsExtension {
                 parameters: vec![
                     param!("volume_id", "ID of the volume", ParamType::String, required),
                     param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("wait", "Block until the volume is available", ParamType::Boolean, optional, json!(false)),
                 ],
             }),
             "create_snapshot" => Some(ActionDefinition {
@@ -726,6 +1733,22 @@ impl CpiExtension for AwsExtension {
                     param!("volume_id", "ID of the volume", ParamType::String, required),
                     param!("snapshot_name", "Name of the snapshot", ParamType::String, required),
                     param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("wait", "Block until the snapshot is completed", ParamType::Boolean, optional, json!(false)),
+                    param!("timeout_secs", "Override the default ~5 minute wait timeout", ParamType::Integer, optional, json!(300)),
+                ],
+            }),
+            "copy_snapshot" => Some(ActionDefinition {
+                name: "copy_snapshot".to_string(),
+                description: "Copy an EBS snapshot from one region into another".to_string(),
+                parameters: vec![
+                    param!("source_snapshot_id", "ID of the snapshot to copy", ParamType::String, required),
+                    param!("source_region", "Region the source snapshot lives in", ParamType::String, required),
+                    param!("region", "Destination region for the copy", ParamType::String, optional, json!("us-east-1")),
+                    param!("name", "Name tag for the copied snapshot", ParamType::String, optional, json!("")),
+                    param!("description", "Description for the copied snapshot", ParamType::String, optional, json!("")),
+                    param!("snapshot_id", "Alias for source_snapshot_id", ParamType::String, optional, json!("")),
+                    param!("snapshot_name", "Alias for name", ParamType::String, optional, json!("")),
+                    param!("destination_region", "Alias for region", ParamType::String, optional, json!("")),
                 ],
             }),
             "delete_snapshot" => Some(ActionDefinition {
@@ -750,6 +1773,8 @@ impl CpiExtension for AwsExtension {
                 parameters: vec![
                     param!("worker_id", "ID of the instance", ParamType::String, required),
                     param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("wait", "Block until the instance is running again", ParamType::Boolean, optional, json!(false)),
+                    param!("timeout_secs", "Override the default ~5 minute wait timeout", ParamType::Integer, optional, json!(300)),
                 ],
             }),
             "set_worker_metadata" => Some(ActionDefinition {
@@ -769,6 +1794,95 @@ impl CpiExtension for AwsExtension {
                     param!("source_volume_id", "ID of the source volume", ParamType::String, required),
                     param!("snapshot_name", "Name for the snapshot", ParamType::String, required),
                     param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                    param!("wait", "Block until the snapshot is completed", ParamType::Boolean, optional, json!(false)),
+                    param!("timeout_secs", "Override the default ~5 minute wait timeout", ParamType::Integer, optional, json!(300)),
+                ],
+            }),
+            "restore_root_volume" => Some(ActionDefinition {
+                name: "restore_root_volume".to_string(),
+                description: "Restore an instance's root volume from its latest snapshot".to_string(),
+                parameters: vec![
+                    param!("worker_id", "ID of the instance to restore", ParamType::String, required),
+                    param!("perform_swap", "Stop the instance and swap in the restored volume", ParamType::Boolean, optional, json!(true)),
+                    param!("restart", "Start the instance again after swapping the volume", ParamType::Boolean, optional, json!(true)),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "list_security_groups" => Some(ActionDefinition {
+                name: "list_security_groups".to_string(),
+                description: "List all EC2 security groups".to_string(),
+                parameters: vec![
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "create_security_group" => Some(ActionDefinition {
+                name: "create_security_group".to_string(),
+                description: "Create a new EC2 security group".to_string(),
+                parameters: vec![
+                    param!("name", "Name of the security group", ParamType::String, required),
+                    param!("description", "Description of the security group", ParamType::String, required),
+                    param!("vpc_id", "VPC to create the security group in", ParamType::String, optional, json!("")),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "delete_security_group" => Some(ActionDefinition {
+                name: "delete_security_group".to_string(),
+                description: "Delete an EC2 security group".to_string(),
+                parameters: vec![
+                    param!("group_id", "ID of the security group", ParamType::String, required),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "authorize_ingress" => Some(ActionDefinition {
+                name: "authorize_ingress".to_string(),
+                description: "Authorize an ingress rule on a security group".to_string(),
+                parameters: vec![
+                    param!("group_id", "ID of the security group", ParamType::String, required),
+                    param!("protocol", "IP protocol (tcp, udp, icmp, -1 for all)", ParamType::String, required),
+                    param!("from_port", "Start of the port range", ParamType::Integer, required),
+                    param!("to_port", "End of the port range", ParamType::Integer, required),
+                    param!("cidr", "CIDR block allowed to connect", ParamType::String, optional, json!("")),
+                    param!("source_group_id", "Security group ID allowed to connect", ParamType::String, optional, json!("")),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "authorize_egress" => Some(ActionDefinition {
+                name: "authorize_egress".to_string(),
+                description: "Authorize an egress rule on a security group".to_string(),
+                parameters: vec![
+                    param!("group_id", "ID of the security group", ParamType::String, required),
+                    param!("protocol", "IP protocol (tcp, udp, icmp, -1 for all)", ParamType::String, required),
+                    param!("from_port", "Start of the port range", ParamType::Integer, required),
+                    param!("to_port", "End of the port range", ParamType::Integer, required),
+                    param!("cidr", "CIDR block the group is allowed to connect to", ParamType::String, optional, json!("")),
+                    param!("source_group_id", "Security group ID the group is allowed to connect to", ParamType::String, optional, json!("")),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "revoke_ingress" => Some(ActionDefinition {
+                name: "revoke_ingress".to_string(),
+                description: "Revoke an ingress rule from a security group".to_string(),
+                parameters: vec![
+                    param!("group_id", "ID of the security group", ParamType::String, required),
+                    param!("protocol", "IP protocol (tcp, udp, icmp, -1 for all)", ParamType::String, required),
+                    param!("from_port", "Start of the port range", ParamType::Integer, required),
+                    param!("to_port", "End of the port range", ParamType::Integer, required),
+                    param!("cidr", "CIDR block to revoke", ParamType::String, optional, json!("")),
+                    param!("source_group_id", "Security group ID to revoke", ParamType::String, optional, json!("")),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
+                ],
+            }),
+            "revoke_egress" => Some(ActionDefinition {
+                name: "revoke_egress".to_string(),
+                description: "Revoke an egress rule from a security group".to_string(),
+                parameters: vec![
+                    param!("group_id", "ID of the security group", ParamType::String, required),
+                    param!("protocol", "IP protocol (tcp, udp, icmp, -1 for all)", ParamType::String, required),
+                    param!("from_port", "Start of the port range", ParamType::Integer, required),
+                    param!("to_port", "End of the port range", ParamType::Integer, required),
+                    param!("cidr", "CIDR block to revoke", ParamType::String, optional, json!("")),
+                    param!("source_group_id", "Security group ID to revoke", ParamType::String, optional, json!("")),
+                    param!("region", "AWS region", ParamType::String, optional, json!("us-east-1")),
                 ],
             }),
             _ => None,
@@ -776,63 +1890,121 @@ impl CpiExtension for AwsExtension {
     }
     
     fn execute_action(&self, action: &str, params: &HashMap<String, Value>) -> ActionResult {
-        // Create a runtime for executing async functions
-        let runtime = tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
-            .map_err(|e| format!("Failed to create runtime: {}", e))?;
-        
-        // Clone self to create a mutable version for the async functions
-        let mut aws_ext = AwsExtension {
-            name: self.name.clone(),
-            provider_type: self.provider_type.clone(),
-            default_settings: self.default_settings.clone(),
-            ec2_client: self.ec2_client.clone(),
-        };
-            
         // Extract common region parameter
         let region = validation::extract_string_opt(params, "region").ok().flatten();
         let region_ref = region.as_deref();
-        
+
+        // Extract the common optional "wait" flag used by actions that can block until a
+        // resource reaches its target state, plus an optional override for the default
+        // ~5 minute polling timeout used by those waits.
+        let wait = validation::extract_bool_opt(params, "wait").ok().flatten().unwrap_or(false);
+        let timeout_secs = validation::extract_int(params, "timeout_secs").ok().map(|v| v as u64);
+
         // Execute the appropriate action
         match action {
-            "test_install" => runtime.block_on(async { aws_ext.test_install() }),
-            
-            "list_workers" => runtime.block_on(aws_ext.list_workers(region_ref)),
-            
+            "test_install" => self.runtime.block_on(self.test_install(region_ref)),
+
+            "list_workers" => self.runtime.block_on(self.list_workers(region_ref)),
+
             "create_worker" => {
                 let worker_name = validation::extract_string(params, "worker_name")?;
                 let instance_type = validation::extract_string_opt(params, "instance_type")?.unwrap_or_else(|| "t2.micro".to_string());
                 let ami = validation::extract_string_opt(params, "ami")?.unwrap_or_else(|| "ami-0c55b159cbfafe1f0".to_string());
-                
-                runtime.block_on(aws_ext.create_worker(worker_name, instance_type, ami, region_ref))
+                let security_group_ids = params.get("security_group_ids")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect::<Vec<String>>())
+                    .filter(|ids| !ids.is_empty());
+                let key_name = validation::extract_string_opt(params, "key_name")?.filter(|s| !s.is_empty());
+                let user_data = validation::extract_string_opt(params, "user_data")?.filter(|s| !s.is_empty());
+                let subnet_id = validation::extract_string_opt(params, "subnet_id")?.filter(|s| !s.is_empty());
+                let associate_public_ip = validation::extract_bool_opt(params, "associate_public_ip").ok().flatten();
+                let root_volume_size_gb = validation::extract_int(params, "root_volume_size_gb").ok().map(|v| v as i32).filter(|v| *v > 0);
+                let root_volume_type = validation::extract_string_opt(params, "root_volume_type")?.filter(|s| !s.is_empty());
+
+                self.runtime.block_on(self.create_worker(
+                    worker_name, instance_type, ami, security_group_ids,
+                    key_name, user_data, subnet_id, associate_public_ip,
+                    root_volume_size_gb, root_volume_type, region_ref, wait,
+                ))
             },
-            
+
             "delete_worker" => {
                 let worker_id = validation::extract_string(params, "worker_id")?;
-                runtime.block_on(aws_ext.delete_worker(worker_id, region_ref))
+                self.runtime.block_on(self.delete_worker(worker_id, region_ref, wait))
             },
-            
+
+            "wait_for_worker_state" => {
+                let worker_id = validation::extract_string(params, "worker_id")?;
+                let target_state = validation::extract_string_opt(params, "target_state")?.unwrap_or_else(|| "running".to_string());
+                self.runtime.block_on(self.wait_for_worker_state(worker_id, target_state, region_ref, timeout_secs))
+            },
+
             "get_worker" => {
                 let worker_id = validation::extract_string(params, "worker_id")?;
-                runtime.block_on(aws_ext.get_worker(worker_id, region_ref))
+                self.runtime.block_on(self.get_worker(worker_id, region_ref))
             },
             
             "has_worker" => {
                 let worker_id = validation::extract_string(params, "worker_id")?;
-                runtime.block_on(aws_ext.has_worker(worker_id, region_ref))
+                self.runtime.block_on(self.has_worker(worker_id, region_ref))
             },
             
             "start_worker" => {
                 let worker_id = validation::extract_string(params, "worker_id")?;
-                runtime.block_on(aws_ext.start_worker(worker_id, region_ref))
+                self.runtime.block_on(self.start_worker(worker_id, region_ref, wait, timeout_secs))
+            },
+
+            "stop_worker" => {
+                let worker_id = validation::extract_string(params, "worker_id")?;
+                let force = validation::extract_bool_opt(params, "force").ok().flatten().unwrap_or(false);
+                let hibernate = validation::extract_bool_opt(params, "hibernate").ok().flatten().unwrap_or(false);
+
+                self.runtime.block_on(self.stop_worker(worker_id, force, hibernate, region_ref, wait, timeout_secs))
+            },
+
+            "start_workers" => {
+                let worker_ids = self.extract_required_string_array(params, "worker_ids")?;
+                self.runtime.block_on(self.start_workers(worker_ids, region_ref))
+            },
+
+            "stop_workers" => {
+                let worker_ids = self.extract_required_string_array(params, "worker_ids")?;
+                let force = validation::extract_bool_opt(params, "force").ok().flatten().unwrap_or(false);
+                let hibernate = validation::extract_bool_opt(params, "hibernate").ok().flatten().unwrap_or(false);
+
+                self.runtime.block_on(self.stop_workers(worker_ids, force, hibernate, region_ref))
+            },
+
+            "reboot_workers" => {
+                let worker_ids = self.extract_required_string_array(params, "worker_ids")?;
+                self.runtime.block_on(self.reboot_workers(worker_ids, region_ref))
+            },
+
+            "delete_workers" => {
+                let worker_ids = self.extract_required_string_array(params, "worker_ids")?;
+                self.runtime.block_on(self.delete_workers(worker_ids, region_ref))
+            },
+
+            "get_volumes" => self.runtime.block_on(self.get_volumes(region_ref)),
+
+            "get_volumes_by_instance" => {
+                let worker_id = validation::extract_string(params, "worker_id")?;
+                self.runtime.block_on(self.get_volumes_by_instance(worker_id, region_ref))
+            },
+
+            "snapshot_instance" => {
+                let worker_id = validation::extract_string(params, "worker_id")?;
+                let exclude = params.get("exclude")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect::<Vec<String>>())
+                    .unwrap_or_default();
+
+                self.runtime.block_on(self.snapshot_instance(worker_id, exclude, region_ref))
             },
-            
-            "get_volumes" => runtime.block_on(aws_ext.get_volumes(region_ref)),
             
             "has_volume" => {
                 let volume_id = validation::extract_string(params, "volume_id")?;
-                runtime.block_on(aws_ext.has_volume(volume_id, region_ref))
+                self.runtime.block_on(self.has_volume(volume_id, region_ref))
             },
             
             "create_volume" => {
@@ -840,47 +2012,62 @@ impl CpiExtension for AwsExtension {
                 let availability_zone = validation::extract_string(params, "availability_zone")?;
                 let volume_type = validation::extract_string_opt(params, "volume_type")?.unwrap_or_else(|| "gp2".to_string());
                 
-                runtime.block_on(aws_ext.create_volume(size_gb, availability_zone, volume_type, region_ref))
+                self.runtime.block_on(self.create_volume(size_gb, availability_zone, volume_type, region_ref, wait, timeout_secs))
             },
             
             "delete_volume" => {
                 let volume_id = validation::extract_string(params, "volume_id")?;
-                runtime.block_on(aws_ext.delete_volume(volume_id, region_ref))
+                self.runtime.block_on(self.delete_volume(volume_id, region_ref))
             },
             
             "attach_volume" => {
                 let worker_id = validation::extract_string(params, "worker_id")?;
                 let volume_id = validation::extract_string(params, "volume_id")?;
                 let device_name = validation::extract_string(params, "device_name")?;
-                
-                runtime.block_on(aws_ext.attach_volume(worker_id, volume_id, device_name, region_ref))
+
+                self.runtime.block_on(self.attach_volume(worker_id, volume_id, device_name, region_ref, wait))
             },
-            
+
             "detach_volume" => {
                 let volume_id = validation::extract_string(params, "volume_id")?;
-                runtime.block_on(aws_ext.detach_volume(volume_id, region_ref))
+                self.runtime.block_on(self.detach_volume(volume_id, region_ref, wait))
             },
             
             "create_snapshot" => {
                 let volume_id = validation::extract_string(params, "volume_id")?;
                 let snapshot_name = validation::extract_string(params, "snapshot_name")?;
                 
-                runtime.block_on(aws_ext.create_snapshot(volume_id, snapshot_name, region_ref))
+                self.runtime.block_on(self.create_snapshot(volume_id, snapshot_name, region_ref, wait, timeout_secs))
             },
             
+            "copy_snapshot" => {
+                // Accept both `source_snapshot_id`/`name`/`region` and the `snapshot_id`/
+                // `snapshot_name`/`destination_region` aliases callers sometimes use.
+                let source_snapshot_id = validation::extract_string(params, "source_snapshot_id")
+                    .or_else(|_| validation::extract_string(params, "snapshot_id"))?;
+                let source_region = validation::extract_string(params, "source_region")?;
+                let name = validation::extract_string_opt(params, "name")?.filter(|s| !s.is_empty())
+                    .or_else(|| validation::extract_string_opt(params, "snapshot_name").ok().flatten().filter(|s| !s.is_empty()));
+                let description = validation::extract_string_opt(params, "description")?.filter(|s| !s.is_empty());
+                let destination_region = validation::extract_string_opt(params, "destination_region")?.filter(|s| !s.is_empty());
+                let destination_region_ref = destination_region.as_deref().or(region_ref);
+
+                self.runtime.block_on(self.copy_snapshot(source_snapshot_id, source_region, destination_region_ref, name, description))
+            },
+
             "delete_snapshot" => {
                 let snapshot_id = validation::extract_string(params, "snapshot_id")?;
-                runtime.block_on(aws_ext.delete_snapshot(snapshot_id, region_ref))
+                self.runtime.block_on(self.delete_snapshot(snapshot_id, region_ref))
             },
             
             "has_snapshot" => {
                 let snapshot_id = validation::extract_string(params, "snapshot_id")?;
-                runtime.block_on(aws_ext.has_snapshot(snapshot_id, region_ref))
+                self.runtime.block_on(self.has_snapshot(snapshot_id, region_ref))
             },
             
             "reboot_worker" => {
                 let worker_id = validation::extract_string(params, "worker_id")?;
-                runtime.block_on(aws_ext.reboot_worker(worker_id, region_ref))
+                self.runtime.block_on(self.reboot_worker(worker_id, region_ref, wait, timeout_secs))
             },
             
             "set_worker_metadata" => {
@@ -888,16 +2075,83 @@ impl CpiExtension for AwsExtension {
                 let key = validation::extract_string(params, "key")?;
                 let value = validation::extract_string(params, "value")?;
                 
-                runtime.block_on(aws_ext.set_worker_metadata(worker_id, key, value, region_ref))
+                self.runtime.block_on(self.set_worker_metadata(worker_id, key, value, region_ref))
             },
             
             "snapshot_volume" => {
                 let source_volume_id = validation::extract_string(params, "source_volume_id")?;
                 let snapshot_name = validation::extract_string(params, "snapshot_name")?;
-                
-                runtime.block_on(aws_ext.snapshot_volume(source_volume_id, snapshot_name, region_ref))
+
+                self.runtime.block_on(self.snapshot_volume(source_volume_id, snapshot_name, region_ref, wait, timeout_secs))
             },
-            
+
+            "restore_root_volume" => {
+                let worker_id = validation::extract_string(params, "worker_id")?;
+                let perform_swap = validation::extract_bool_opt(params, "perform_swap").ok().flatten().unwrap_or(true);
+                let restart = validation::extract_bool_opt(params, "restart").ok().flatten().unwrap_or(true);
+
+                self.runtime.block_on(self.restore_root_volume(worker_id, perform_swap, restart, region_ref))
+            },
+
+            "list_security_groups" => self.runtime.block_on(self.list_security_groups(region_ref)),
+
+            "create_security_group" => {
+                let name = validation::extract_string(params, "name")?;
+                let description = validation::extract_string(params, "description")?;
+                let vpc_id = validation::extract_string_opt(params, "vpc_id")?.filter(|s| !s.is_empty());
+
+                self.runtime.block_on(self.create_security_group(name, description, vpc_id, region_ref))
+            },
+
+            "delete_security_group" => {
+                let group_id = validation::extract_string(params, "group_id")?;
+                self.runtime.block_on(self.delete_security_group(group_id, region_ref))
+            },
+
+            "authorize_ingress" => {
+                let group_id = validation::extract_string(params, "group_id")?;
+                let protocol = validation::extract_string(params, "protocol")?;
+                let from_port = validation::extract_int(params, "from_port")? as i32;
+                let to_port = validation::extract_int(params, "to_port")? as i32;
+                let cidr = validation::extract_string_opt(params, "cidr")?.filter(|s| !s.is_empty());
+                let source_group_id = validation::extract_string_opt(params, "source_group_id")?.filter(|s| !s.is_empty());
+
+                self.runtime.block_on(self.authorize_ingress(group_id, protocol, from_port, to_port, cidr, source_group_id, region_ref))
+            },
+
+            "authorize_egress" => {
+                let group_id = validation::extract_string(params, "group_id")?;
+                let protocol = validation::extract_string(params, "protocol")?;
+                let from_port = validation::extract_int(params, "from_port")? as i32;
+                let to_port = validation::extract_int(params, "to_port")? as i32;
+                let cidr = validation::extract_string_opt(params, "cidr")?.filter(|s| !s.is_empty());
+                let source_group_id = validation::extract_string_opt(params, "source_group_id")?.filter(|s| !s.is_empty());
+
+                self.runtime.block_on(self.authorize_egress(group_id, protocol, from_port, to_port, cidr, source_group_id, region_ref))
+            },
+
+            "revoke_ingress" => {
+                let group_id = validation::extract_string(params, "group_id")?;
+                let protocol = validation::extract_string(params, "protocol")?;
+                let from_port = validation::extract_int(params, "from_port")? as i32;
+                let to_port = validation::extract_int(params, "to_port")? as i32;
+                let cidr = validation::extract_string_opt(params, "cidr")?.filter(|s| !s.is_empty());
+                let source_group_id = validation::extract_string_opt(params, "source_group_id")?.filter(|s| !s.is_empty());
+
+                self.runtime.block_on(self.revoke_ingress(group_id, protocol, from_port, to_port, cidr, source_group_id, region_ref))
+            },
+
+            "revoke_egress" => {
+                let group_id = validation::extract_string(params, "group_id")?;
+                let protocol = validation::extract_string(params, "protocol")?;
+                let from_port = validation::extract_int(params, "from_port")? as i32;
+                let to_port = validation::extract_int(params, "to_port")? as i32;
+                let cidr = validation::extract_string_opt(params, "cidr")?.filter(|s| !s.is_empty());
+                let source_group_id = validation::extract_string_opt(params, "source_group_id")?.filter(|s| !s.is_empty());
+
+                self.runtime.block_on(self.revoke_egress(group_id, protocol, from_port, to_port, cidr, source_group_id, region_ref))
+            },
+
             _ => Err(format!("Action '{}' not found", action)),
         }
     }